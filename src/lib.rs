@@ -1,24 +1,51 @@
 //! # filepath
 //!
-//! `filepath` contains an extension trait for `std::fs::File` providing a `path` method.
+//! `filepath` contains an extension trait providing a `path` method for anything that
+//! behaves like an open file descriptor or handle, plus the underlying free functions
+//! for callers who only have a borrowed descriptor.
 //!
 
-use std::fs::File;
+use std::ffi::OsString;
 use std::io;
 use std::path::PathBuf;
 
 #[cfg(unix)]
-use std::os::unix::io::AsRawFd;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd};
 
-/// An extension trait for `std::fs::File` providing a `path` method.
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle};
+
+/// The result of checking whether a resolved path still refers to the open file it was
+/// resolved from. See [`FilePath::path_status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathStatus {
+    /// The path still refers to the same file as the open descriptor/handle.
+    Live(PathBuf),
+    /// The file has been unlinked (Unix) or has no surviving path (Windows); the path
+    /// is the last known location of the file before it was removed.
+    Deleted(PathBuf),
+    /// The path resolved to a different file than the open descriptor/handle, for
+    /// example because the original file was moved and something else was created at
+    /// its old location.
+    Stale,
+}
+
+/// An extension trait providing a `path` method for any type that exposes a borrowed
+/// file descriptor (`AsFd`) or handle (`AsHandle`), such as `std::fs::File`,
+/// `std::net::TcpStream`, `OwnedFd`/`OwnedHandle`, or `BorrowedFd`/`BorrowedHandle`
+/// themselves.
 pub trait FilePath {
     /// Returns the path of this file.
     ///
     /// The path might be wrong for example after moving a file.
     ///
     /// # Platform-specific behavior
-    /// This function currently uses `/proc/self/fd/` on Linux, `fcntl` with `F_GETPATH` on macOS
-    /// and `GetFinalPathNameByHandle` on Windows.
+    /// This function currently uses `/proc/self/fd/` on Linux, `fcntl` with `F_GETPATH` on macOS,
+    /// `GetFinalPathNameByHandle` on Windows, and `/proc/self/path/` on Solaris/illumos. On
+    /// FreeBSD it uses `fcntl` with `F_KINFO` (13+) or a `sysctl` lookup as a fallback, which has
+    /// to walk every open file descriptor of the process and is therefore slower than the other
+    /// approaches. NetBSD and OpenBSD have no fd-to-path mapping in their `kinfo_file` sysctl
+    /// output at all, so this returns an `Unsupported` error on those two targets.
     ///
     /// # Examples
     ///
@@ -34,89 +61,589 @@ pub trait FilePath {
     /// }
     /// ```
     fn path(&self) -> io::Result<PathBuf>;
+
+    /// Checks whether the path returned by [`path`](FilePath::path) still refers to
+    /// this open file, and returns a [`PathStatus`] describing the result instead of
+    /// a bare `PathBuf`.
+    ///
+    /// This is useful because a resolved path can go stale: the file it named may
+    /// have been deleted, or renamed/replaced so that the path now points somewhere
+    /// else entirely.
+    ///
+    /// # Platform-specific behavior
+    /// On Unix this compares `(st_dev, st_ino)` between the open descriptor (via
+    /// `fstat`) and the resolved path (via `stat`); on Linux, once that `stat` call
+    /// has confirmed the path doesn't exist, a literal ` (deleted)` suffix on the
+    /// `/proc/self/fd/` link target is stripped so `Deleted` reports the file's real
+    /// last-known path rather than the raw `/proc` link text. On Windows this
+    /// compares the volume serial number and file index from
+    /// `GetFileInformationByHandle` between the handle and a fresh `File::open` of
+    /// the resolved path.
+    fn path_status(&self) -> io::Result<PathStatus>;
+
+    /// Like [`path`](FilePath::path), but lets the caller choose the volume-name and
+    /// file-name format documented for `GetFinalPathNameByHandleW`, instead of always
+    /// getting the normalized DOS-drive path.
+    ///
+    /// # Platform-specific behavior
+    /// Windows-only. The `\\?\`/UNC prefix rewriting that [`path`](FilePath::path)
+    /// applies is only meaningful for [`VolumeNameFormat::Dos`]; other volume name
+    /// formats are returned exactly as `GetFinalPathNameByHandleW` produced them.
+    #[cfg(windows)]
+    fn path_with(&self, format: PathFormat) -> io::Result<PathBuf>;
+
+    /// Returns the raw OS path of this file, with no postprocessing of what the
+    /// underlying OS call returned.
+    ///
+    /// # Platform-specific behavior
+    /// On Linux/macOS this returns the same bytes as [`path`](FilePath::path), since
+    /// both already hand back the kernel's bytes unmodified. On Windows this differs
+    /// from [`path`](FilePath::path), which strips the `\\?\` prefix and rewrites
+    /// `\\?\UNC\` for readability: `path_verbatim` instead returns the wide string
+    /// exactly as `GetFinalPathNameByHandleW` produced it. This matters for
+    /// extended-length paths (>260 chars) or volume-GUID paths that must be passed
+    /// verbatim back to other Win32 APIs.
+    fn path_verbatim(&self) -> io::Result<OsString>;
 }
 
-impl FilePath for File {
-    #[cfg(target_os = "linux")]
+#[cfg(unix)]
+impl<T: AsFd> FilePath for T {
     fn path(&self) -> io::Result<PathBuf> {
-        use std::path::Path;
+        path_of_fd(self.as_fd())
+    }
+
+    fn path_status(&self) -> io::Result<PathStatus> {
+        path_status_of_fd(self.as_fd())
+    }
 
-        let fd = self.as_raw_fd();
-        let path = Path::new("/proc/self/fd/").join(fd.to_string());
-        std::fs::read_link(path)
+    fn path_verbatim(&self) -> io::Result<OsString> {
+        path_verbatim_of_fd(self.as_fd())
     }
+}
 
-    #[cfg(any(target_os = "macos", target_os = "ios"))]
+#[cfg(windows)]
+impl<T: AsHandle> FilePath for T {
     fn path(&self) -> io::Result<PathBuf> {
-        use std::ffi::OsString;
-        use std::os::unix::ffi::OsStringExt;
-        const F_GETPATH: i32 = 50;
+        path_of_handle(self.as_handle())
+    }
 
-        let fd = self.as_raw_fd();
-        let mut path = vec![0; libc::PATH_MAX as usize + 1];
+    fn path_status(&self) -> io::Result<PathStatus> {
+        path_status_of_handle(self.as_handle())
+    }
 
-        unsafe {
-            if libc::fcntl(fd, F_GETPATH, path.as_mut_ptr()) < 0 {
-                return Err(io::Error::last_os_error());
-            }
+    fn path_with(&self, format: PathFormat) -> io::Result<PathBuf> {
+        path_of_handle_with(self.as_handle(), format)
+    }
+
+    fn path_verbatim(&self) -> io::Result<OsString> {
+        path_verbatim_of_handle(self.as_handle())
+    }
+}
+
+/// Returns the path of an open file descriptor, without taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path`], for callers who have a
+/// `BorrowedFd` (for example one obtained from FFI or from another process) and don't
+/// want to wrap it in a `File` first, which would take ownership of the descriptor.
+///
+/// See [`FilePath::path`] for platform-specific behavior.
+#[cfg(target_os = "linux")]
+pub fn path_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathBuf> {
+    use std::path::Path;
+
+    let path = Path::new("/proc/self/fd/").join(fd.as_raw_fd().to_string());
+    std::fs::read_link(path)
+}
+
+/// Returns the path of an open file descriptor, without taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path`], for callers who have a
+/// `BorrowedFd` (for example one obtained from FFI or from another process) and don't
+/// want to wrap it in a `File` first, which would take ownership of the descriptor.
+///
+/// See [`FilePath::path`] for platform-specific behavior.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub fn path_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+    const F_GETPATH: i32 = 50;
+
+    let mut path = vec![0; libc::PATH_MAX as usize + 1];
+
+    unsafe {
+        if libc::fcntl(fd.as_raw_fd(), F_GETPATH, path.as_mut_ptr()) < 0 {
+            return Err(io::Error::last_os_error());
         }
+    }
+
+    path.retain(|&c| c != 0);
+    Ok(PathBuf::from(OsString::from_vec(path)))
+}
+
+/// Returns the path of an open file descriptor, without taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path`], for callers who have a
+/// `BorrowedFd` (for example one obtained from FFI or from another process) and don't
+/// want to wrap it in a `File` first, which would take ownership of the descriptor.
+///
+/// See [`FilePath::path`] for platform-specific behavior.
+#[cfg(target_os = "freebsd")]
+pub fn path_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathBuf> {
+    use std::ffi::OsString;
+    use std::mem;
+    use std::os::unix::ffi::OsStringExt;
+
+    // FreeBSD 13+ can describe a single fd directly via F_KINFO, which is both
+    // simpler and cheaper than scanning every open fd through sysctl below.
+    let raw_fd = fd.as_raw_fd();
+    let mut kif: libc::kinfo_file = unsafe { mem::zeroed() };
+    kif.kf_structsize = mem::size_of::<libc::kinfo_file>() as libc::c_int;
 
-        path.retain(|&c| c != 0);
-        Ok(PathBuf::from(OsString::from_vec(path)))
+    if unsafe { libc::fcntl(raw_fd, libc::F_KINFO, &mut kif) } == 0 {
+        let path: Vec<u8> = kif
+            .kf_path
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+        return Ok(PathBuf::from(OsString::from_vec(path)));
     }
 
-    #[cfg(windows)]
-    fn path(&self) -> std::io::Result<PathBuf> {
-        use std::ffi::OsString;
-        use std::os::windows::{ffi::OsStringExt, io::AsRawHandle};
-        use windows::Win32::{
-            Foundation,
-            Storage::FileSystem::{GetFinalPathNameByHandleW, GETFINALPATHNAMEBYHANDLE_FLAGS},
+    // Older FreeBSD has no F_KINFO; fall back to the slower sysctl-based lookup.
+    bsd::path_via_sysctl(raw_fd)
+}
+
+/// Returns the path of an open file descriptor, without taking ownership of it.
+///
+/// Unlike FreeBSD's, NetBSD's and OpenBSD's `kinfo_file` sysctl output carries no path
+/// for a file descriptor at all (NetBSD's struct only exposes `ki_fd`/`ki_pid`,
+/// OpenBSD's only `fd_fd`), so there is no mechanism on either platform to resolve a
+/// descriptor back to a path. This always returns an `io::ErrorKind::Unsupported`
+/// error.
+#[cfg(any(target_os = "netbsd", target_os = "openbsd"))]
+pub fn path_of_fd(_fd: BorrowedFd<'_>) -> io::Result<PathBuf> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "resolving a file descriptor to a path is not supported on NetBSD/OpenBSD",
+    ))
+}
+
+/// Returns the path of an open file descriptor, without taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path`], for callers who have a
+/// `BorrowedFd` (for example one obtained from FFI or from another process) and don't
+/// want to wrap it in a `File` first, which would take ownership of the descriptor.
+///
+/// See [`FilePath::path`] for platform-specific behavior.
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+pub fn path_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathBuf> {
+    use std::path::Path;
+
+    let path = Path::new("/proc/self/path").join(fd.as_raw_fd().to_string());
+    std::fs::read_link(path)
+}
+
+/// Returns the raw OS path of an open file descriptor, without taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path_verbatim`]. On Unix this is
+/// identical to [`path_of_fd`], since the platform-specific implementations already
+/// hand back the kernel's bytes unmodified.
+#[cfg(unix)]
+pub fn path_verbatim_of_fd(fd: BorrowedFd<'_>) -> io::Result<OsString> {
+    path_of_fd(fd).map(PathBuf::into_os_string)
+}
+
+/// Which volume naming convention [`FilePath::path_with`] should resolve to, mirroring
+/// the `VOLUME_NAME_*` flags documented for `GetFinalPathNameByHandleW`.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeNameFormat {
+    /// The normalized DOS drive-letter path (e.g. `C:\dir\file`), with the `\\?\`
+    /// prefix stripped. This is the format used by [`FilePath::path`].
+    Dos,
+    /// The `\\?\Volume{GUID}\` form, which resolves even for volumes without a drive
+    /// letter mounted.
+    Guid,
+    /// The kernel `\Device\HarddiskVolumeN\` path.
+    Nt,
+}
+
+/// Which file naming convention [`FilePath::path_with`] should resolve to, mirroring
+/// the `FILE_NAME_*` flags documented for `GetFinalPathNameByHandleW`.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileNameFormat {
+    /// The normalized name, with any short (8.3) name components expanded. This is
+    /// the format used by [`FilePath::path`].
+    Normalized,
+    /// The exact name used to open the handle, without short-name expansion.
+    Opened,
+}
+
+/// The path format requested from [`FilePath::path_with`]. Defaults to the same
+/// format [`FilePath::path`] uses: [`VolumeNameFormat::Dos`] and
+/// [`FileNameFormat::Normalized`].
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathFormat {
+    pub volume_name: VolumeNameFormat,
+    pub file_name: FileNameFormat,
+}
+
+#[cfg(windows)]
+impl Default for PathFormat {
+    fn default() -> Self {
+        PathFormat {
+            volume_name: VolumeNameFormat::Dos,
+            file_name: FileNameFormat::Normalized,
+        }
+    }
+}
+
+#[cfg(windows)]
+impl PathFormat {
+    fn to_flags(self) -> windows::Win32::Storage::FileSystem::GETFINALPATHNAMEBYHANDLE_FLAGS {
+        use windows::Win32::Storage::FileSystem::{
+            FILE_NAME_NORMALIZED, FILE_NAME_OPENED, VOLUME_NAME_DOS, VOLUME_NAME_GUID,
+            VOLUME_NAME_NT,
         };
 
-        // Call with null to get the required size.
-        let len = unsafe {
-            let handle = Foundation::HANDLE(self.as_raw_handle());
-            GetFinalPathNameByHandleW(handle, &mut [], GETFINALPATHNAMEBYHANDLE_FLAGS(0))
+        let volume_name = match self.volume_name {
+            VolumeNameFormat::Dos => VOLUME_NAME_DOS,
+            VolumeNameFormat::Guid => VOLUME_NAME_GUID,
+            VolumeNameFormat::Nt => VOLUME_NAME_NT,
+        };
+        let file_name = match self.file_name {
+            FileNameFormat::Normalized => FILE_NAME_NORMALIZED,
+            FileNameFormat::Opened => FILE_NAME_OPENED,
         };
-        if len == 0 {
+        volume_name | file_name
+    }
+}
+
+/// Returns the path of an open file handle, without taking ownership of it, using the
+/// default [`PathFormat`].
+///
+/// This is the free-function form of [`FilePath::path`], for callers who have a
+/// `BorrowedHandle` (for example one obtained from FFI or from another process) and
+/// don't want to wrap it in a `File` first, which would take ownership of the handle.
+///
+/// See [`FilePath::path`] for platform-specific behavior.
+#[cfg(windows)]
+pub fn path_of_handle(handle: BorrowedHandle<'_>) -> io::Result<PathBuf> {
+    path_of_handle_with(handle, PathFormat::default())
+}
+
+/// Calls `GetFinalPathNameByHandleW` for `handle` with the given flags and returns the
+/// raw UTF-16 path it produced, with no prefix rewriting applied.
+///
+/// Shared by [`path_of_handle_with`] and [`path_verbatim_of_handle`], which differ only
+/// in what they do with the result.
+#[cfg(windows)]
+fn raw_final_path(
+    handle: BorrowedHandle<'_>,
+    flags: windows::Win32::Storage::FileSystem::GETFINALPATHNAMEBYHANDLE_FLAGS,
+) -> io::Result<Vec<u16>> {
+    use windows::Win32::{Foundation, Storage::FileSystem::GetFinalPathNameByHandleW};
+
+    let raw_handle = Foundation::HANDLE(handle.as_raw_handle());
+
+    // Call with null to get the required size.
+    let len = unsafe { GetFinalPathNameByHandleW(raw_handle, &mut [], flags) };
+    if len == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut path = vec![0; len as usize];
+    let len2 = unsafe { GetFinalPathNameByHandleW(raw_handle, &mut path, flags) };
+    // Handle unlikely case that path length changed between those two calls.
+    if len2 == 0 || len2 >= len {
+        return Err(io::Error::last_os_error());
+    }
+    path.truncate(len2 as usize);
+
+    Ok(path)
+}
+
+/// Returns the path of an open file handle in the requested [`PathFormat`], without
+/// taking ownership of it.
+///
+/// This is the free-function form of [`FilePath::path_with`]. See its documentation
+/// for platform-specific behavior.
+#[cfg(windows)]
+pub fn path_of_handle_with(handle: BorrowedHandle<'_>, format: PathFormat) -> io::Result<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+
+    let path = raw_final_path(handle, format.to_flags())?;
+
+    // The \\?\/UNC prefix rewriting below only makes sense for the DOS-drive form;
+    // GUID and NT paths are returned exactly as the API produced them.
+    if format.volume_name != VolumeNameFormat::Dos {
+        return Ok(PathBuf::from(OsString::from_wide(&path)));
+    }
+
+    // Turn the \\?\UNC\ network path prefix into \\.
+    let prefix = [
+        '\\' as _, '\\' as _, '?' as _, '\\' as _, 'U' as _, 'N' as _, 'C' as _, '\\' as _,
+    ];
+    if path.starts_with(&prefix) {
+        let mut network_path: Vec<u16> = vec!['\\' as u16, '\\' as u16];
+        network_path.extend_from_slice(&path[prefix.len()..]);
+        return Ok(PathBuf::from(OsString::from_wide(&network_path)));
+    }
+
+    // Remove the \\?\ prefix.
+    let prefix = ['\\' as _, '\\' as _, '?' as _, '\\' as _];
+    if path.starts_with(&prefix) {
+        return Ok(PathBuf::from(OsString::from_wide(&path[prefix.len()..])));
+    }
+
+    Ok(PathBuf::from(OsString::from_wide(&path)))
+}
+
+/// Returns the raw OS path of an open file handle, without taking ownership of it and
+/// without any of the `\\?\`/UNC prefix rewriting that [`path_of_handle`] performs.
+///
+/// This is the free-function form of [`FilePath::path_verbatim`].
+#[cfg(windows)]
+pub fn path_verbatim_of_handle(handle: BorrowedHandle<'_>) -> io::Result<OsString> {
+    use std::os::windows::ffi::OsStringExt;
+
+    let path = raw_final_path(handle, PathFormat::default().to_flags())?;
+    Ok(OsString::from_wide(&path))
+}
+
+/// Returns the [`PathStatus`] of an open file descriptor, without taking ownership of
+/// it. This is the free-function form of [`FilePath::path_status`].
+#[cfg(target_os = "linux")]
+pub fn path_status_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathStatus> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    let link = std::fs::read_link(Path::new("/proc/self/fd/").join(fd.as_raw_fd().to_string()))?;
+
+    // Stat the raw link target first, suffix and all. A file whose real name
+    // happens to end in the literal bytes " (deleted)" is still linked and still
+    // stats successfully at that exact path, so it's correctly reported as Live or
+    // Stale without ever going through the suffix handling below.
+    match unix::path_status_from_resolved(fd, link)? {
+        PathStatus::Deleted(raw_path) => {
+            // Only once `stat` has confirmed the raw path doesn't exist do we trust
+            // that a trailing " (deleted)" is the kernel-appended marker for an
+            // unlinked file, and strip it to report the real last-known path.
+            const DELETED_SUFFIX: &[u8] = b" (deleted)";
+            let cleaned = match raw_path.as_os_str().as_bytes().strip_suffix(DELETED_SUFFIX) {
+                Some(stripped) => PathBuf::from(OsStr::from_bytes(stripped)),
+                None => raw_path,
+            };
+            Ok(PathStatus::Deleted(cleaned))
+        }
+        other => Ok(other),
+    }
+}
+
+/// Returns the [`PathStatus`] of an open file descriptor, without taking ownership of
+/// it. This is the free-function form of [`FilePath::path_status`].
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "solaris",
+    target_os = "illumos"
+))]
+pub fn path_status_of_fd(fd: BorrowedFd<'_>) -> io::Result<PathStatus> {
+    let path = path_of_fd(fd)?;
+    unix::path_status_from_resolved(fd, path)
+}
+
+/// Returns the [`PathStatus`] of an open file handle, without taking ownership of it.
+/// This is the free-function form of [`FilePath::path_status`].
+#[cfg(windows)]
+pub fn path_status_of_handle(handle: BorrowedHandle<'_>) -> io::Result<PathStatus> {
+    use std::mem;
+    use windows::Win32::{Foundation, Storage::FileSystem};
+
+    let path = path_of_handle(handle)?;
+
+    let mut info: FileSystem::BY_HANDLE_FILE_INFORMATION = unsafe { mem::zeroed() };
+    let ok = unsafe {
+        FileSystem::GetFileInformationByHandle(
+            Foundation::HANDLE(handle.as_raw_handle()),
+            &mut info,
+        )
+    };
+    if ok == Foundation::FALSE {
+        return Err(io::Error::last_os_error());
+    }
+
+    let file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(PathStatus::Deleted(path)),
+        Err(e) => return Err(e),
+    };
+
+    let mut info2: FileSystem::BY_HANDLE_FILE_INFORMATION = unsafe { mem::zeroed() };
+    let ok2 = unsafe {
+        FileSystem::GetFileInformationByHandle(Foundation::HANDLE(file.as_raw_handle()), &mut info2)
+    };
+    if ok2 == Foundation::FALSE {
+        return Err(io::Error::last_os_error());
+    }
+
+    let same_file = info.dwVolumeSerialNumber == info2.dwVolumeSerialNumber
+        && info.nFileIndexHigh == info2.nFileIndexHigh
+        && info.nFileIndexLow == info2.nFileIndexLow;
+
+    if same_file {
+        Ok(PathStatus::Live(path))
+    } else {
+        Ok(PathStatus::Stale)
+    }
+}
+
+#[cfg(unix)]
+mod unix {
+    use crate::PathStatus;
+    use std::io;
+    use std::mem;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::{AsRawFd, BorrowedFd};
+    use std::path::{Path, PathBuf};
+
+    /// Compares the `(st_dev, st_ino)` of the open fd against the `(st_dev, st_ino)`
+    /// of `path` on disk to determine whether `path` still names the same file.
+    pub(crate) fn path_status_from_resolved(
+        fd: BorrowedFd<'_>,
+        path: PathBuf,
+    ) -> io::Result<PathStatus> {
+        let fd_id = dev_ino_of_fd(fd)?;
+        match dev_ino_of_path(&path) {
+            Ok(path_id) if path_id == fd_id => Ok(PathStatus::Live(path)),
+            Ok(_) => Ok(PathStatus::Stale),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(PathStatus::Deleted(path)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn dev_ino_of_fd(fd: BorrowedFd<'_>) -> io::Result<(u64, u64)> {
+        let mut stat: libc::stat = unsafe { mem::zeroed() };
+        if unsafe { libc::fstat(fd.as_raw_fd(), &mut stat) } < 0 {
             return Err(io::Error::last_os_error());
         }
+        Ok((stat.st_dev as u64, stat.st_ino as u64))
+    }
 
-        let mut path = vec![0; len as usize];
-        let len2 = unsafe {
-            let handle = Foundation::HANDLE(self.as_raw_handle());
-            GetFinalPathNameByHandleW(handle, &mut path, GETFINALPATHNAMEBYHANDLE_FLAGS(0))
-        };
-        // Handle unlikely case that path length changed between those two calls.
-        if len2 == 0 || len2 >= len {
+    fn dev_ino_of_path(path: &Path) -> io::Result<(u64, u64)> {
+        let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path has an interior NUL"))?;
+        let mut stat: libc::stat = unsafe { mem::zeroed() };
+        if unsafe { libc::stat(c_path.as_ptr(), &mut stat) } < 0 {
             return Err(io::Error::last_os_error());
         }
-        path.truncate(len2 as usize);
+        Ok((stat.st_dev as u64, stat.st_ino as u64))
+    }
+}
+
+#[cfg(target_os = "freebsd")]
+mod bsd {
+    use std::ffi::OsString;
+    use std::io;
+    use std::mem;
+    use std::os::unix::ffi::OsStringExt;
+    use std::os::unix::io::RawFd;
+    use std::path::PathBuf;
 
-        // Turn the \\?\UNC\ network path prefix into \\.
-        let prefix = [
-            '\\' as _, '\\' as _, '?' as _, '\\' as _, 'U' as _, 'N' as _, 'C' as _, '\\' as _,
+    /// Finds the path of `fd` by walking every open file descriptor of the current
+    /// process via `sysctl(KERN_PROC_FILEDESC)`, matching `kf_fd` against `fd` and
+    /// reading `kf_path` out of the matching `kinfo_file` record. This is the
+    /// fallback used on FreeBSD before 13 (which lacks `F_KINFO`); it is slower than
+    /// a direct per-fd query since it requires a syscall over the whole fd table.
+    /// This struct layout is FreeBSD-specific and does not apply to NetBSD/OpenBSD.
+    pub(crate) fn path_via_sysctl(fd: RawFd) -> io::Result<PathBuf> {
+        let pid = unsafe { libc::getpid() };
+        let mib = [
+            libc::CTL_KERN,
+            libc::KERN_PROC,
+            libc::KERN_PROC_FILEDESC,
+            pid,
         ];
-        if path.starts_with(&prefix) {
-            let mut network_path: Vec<u16> = vec!['\\' as u16, '\\' as u16];
-            network_path.extend_from_slice(&path[prefix.len()..]);
-            return Ok(PathBuf::from(OsString::from_wide(&network_path)));
+
+        let mut len = 0usize;
+        if unsafe {
+            libc::sysctl(
+                mib.as_ptr(),
+                mib.len() as u32,
+                std::ptr::null_mut(),
+                &mut len,
+                std::ptr::null(),
+                0,
+            )
+        } < 0
+        {
+            return Err(io::Error::last_os_error());
         }
 
-        // Remove the \\?\ prefix.
-        let prefix = ['\\' as _, '\\' as _, '?' as _, '\\' as _];
-        if path.starts_with(&prefix) {
-            return Ok(PathBuf::from(OsString::from_wide(&path[prefix.len()..])));
+        // Leave headroom: the fd table can grow between the size query and the call
+        // that actually fills the buffer.
+        len += len / 2;
+        let mut buf = vec![0u8; len];
+        if unsafe {
+            libc::sysctl(
+                mib.as_ptr(),
+                mib.len() as u32,
+                buf.as_mut_ptr() as *mut _,
+                &mut len,
+                std::ptr::null(),
+                0,
+            )
+        } < 0
+        {
+            return Err(io::Error::last_os_error());
         }
+        buf.truncate(len);
 
-        Ok(PathBuf::from(OsString::from_wide(&path)))
+        // Records are packed: each one is only `kf_structsize` bytes long, usually less
+        // than `size_of::<kinfo_file>()`, so copy what is there into a zeroed struct.
+        let mut offset = 0;
+        while offset + mem::size_of::<libc::c_int>() <= buf.len() {
+            let mut kif: libc::kinfo_file = unsafe { mem::zeroed() };
+            let avail = (buf.len() - offset).min(mem::size_of::<libc::kinfo_file>());
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    buf[offset..].as_ptr(),
+                    &mut kif as *mut libc::kinfo_file as *mut u8,
+                    avail,
+                );
+            }
+            if kif.kf_structsize <= 0 {
+                break;
+            }
+            if kif.kf_fd == fd {
+                let path: Vec<u8> = kif
+                    .kf_path
+                    .iter()
+                    .take_while(|&&c| c != 0)
+                    .map(|&c| c as u8)
+                    .collect();
+                return Ok(PathBuf::from(OsString::from_vec(path)));
+            }
+            offset += kif.kf_structsize as usize;
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no matching kinfo_file entry for this descriptor",
+        ))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::FilePath;
+    use crate::{FilePath, PathStatus};
     use std::fs::{remove_file, File};
     use std::io::prelude::*;
 
@@ -140,4 +667,96 @@ mod tests {
         assert_eq!(buffer, "abc");
         remove_file("bar").unwrap();
     }
+
+    #[test]
+    fn path_status_live() {
+        let file = File::create("status_live").unwrap();
+        assert_eq!(
+            file.path_status().unwrap(),
+            PathStatus::Live(file.path().unwrap())
+        );
+        remove_file("status_live").unwrap();
+    }
+
+    #[test]
+    fn path_verbatim_matches_path() {
+        let file = File::create("verbatim").unwrap();
+
+        // On Unix `path_verbatim` and `path` return the same bytes. On Windows
+        // `path_verbatim` keeps the `\\?\` prefix that `path` strips, so compare
+        // after stripping it back off instead of asserting raw equality.
+        #[cfg(unix)]
+        assert_eq!(
+            file.path_verbatim().unwrap(),
+            file.path().unwrap().into_os_string()
+        );
+        #[cfg(windows)]
+        {
+            let verbatim = file.path_verbatim().unwrap();
+            let verbatim = verbatim.to_str().unwrap();
+            assert!(verbatim.starts_with(r"\\?\"));
+            assert_eq!(&verbatim[4..], file.path().unwrap().to_str().unwrap());
+        }
+
+        remove_file("verbatim").unwrap();
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn path_with_guid_format() {
+        use crate::{FileNameFormat, PathFormat, VolumeNameFormat};
+
+        let file = File::create("path_with_guid").unwrap();
+        let guid_path = file
+            .path_with(PathFormat {
+                volume_name: VolumeNameFormat::Guid,
+                file_name: FileNameFormat::Normalized,
+            })
+            .unwrap();
+        let guid_path = guid_path.to_str().unwrap();
+
+        assert!(guid_path.starts_with(r"\\?\Volume{"));
+        assert_ne!(guid_path, file.path().unwrap().to_str().unwrap());
+
+        remove_file("path_with_guid").unwrap();
+    }
+
+    #[test]
+    fn path_status_deleted() {
+        let file = File::create("status_deleted").unwrap();
+        remove_file("status_deleted").unwrap();
+        assert!(matches!(
+            file.path_status().unwrap(),
+            PathStatus::Deleted(_)
+        ));
+    }
+
+    // The blanket impl covers any AsFd/AsHandle type, not just File. Exercise it on an
+    // OwnedFd/OwnedHandle obtained from a File to make sure the generic impl actually
+    // resolves a path rather than just compiling.
+    #[test]
+    fn path_on_owned_descriptor() {
+        let file = File::create("owned_descriptor").unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::OwnedFd;
+            let owned: OwnedFd = file.into();
+            assert_eq!(
+                owned.path().unwrap().file_name().unwrap(),
+                "owned_descriptor"
+            );
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::io::OwnedHandle;
+            let owned: OwnedHandle = file.into();
+            assert_eq!(
+                owned.path().unwrap().file_name().unwrap(),
+                "owned_descriptor"
+            );
+        }
+
+        remove_file("owned_descriptor").unwrap();
+    }
 }